@@ -0,0 +1,20 @@
+use super::super::util::http_get;
+use super::super::Quote;
+use super::{config_str, quotes_from_json, url_encode, QuoteClient};
+
+/// Fetches quotes from the DummyJSON quotes API (https://dummyjson.com/quotes by default).
+pub struct DummyJsonClient;
+
+impl QuoteClient for DummyJsonClient {
+    fn fetch(&self, query: Option<&str>) -> Result<Vec<Quote>, String> {
+        let endpoint = config_str("dummyjson_endpoint", "https://dummyjson.com/quotes");
+        let url = match query {
+            Some(q) => format!("{}/search?q={}", endpoint, url_encode(q)),
+            None => endpoint,
+        };
+        let body = http_get(&url)?;
+        let author_field = config_str("dummyjson_author_field", "author");
+        let quote_field = config_str("dummyjson_quote_field", "quote");
+        quotes_from_json(&body, &author_field, &quote_field)
+    }
+}
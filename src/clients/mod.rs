@@ -0,0 +1,70 @@
+pub mod dummyjson;
+pub mod quotable;
+
+use std::path::Path;
+
+use super::Quote;
+
+//------------------------------------------------------------------------------------------------------
+// QuoteClient
+//------------------------------------------------------------------------------------------------------
+
+pub trait QuoteClient {
+    fn fetch(&self, query: Option<&str>) -> Result<Vec<Quote>, String>;
+}
+
+//------------------------------------------------------------------------------------------------------
+// Shared helpers
+//------------------------------------------------------------------------------------------------------
+
+// Reads a string value from config.toml, falling back to `default` when the file, or the key
+// within it, is missing. Lets `fetch` be pointed at a different endpoint or field mapping
+// without recompiling.
+pub(crate) fn config_str(key: &str, default: &str) -> String {
+    let path = shellexpand::tilde(super::PATH_CONFIG_FILE).into_owned();
+    if !Path::new(&path).exists() {
+        return default.to_string();
+    }
+    let mut settings = c::Config::default();
+    if settings.merge(c::File::with_name(&path)).is_err() {
+        return default.to_string();
+    }
+    settings.get_str(key).unwrap_or_else(|_| default.to_string())
+}
+
+// Percent-encodes `input` for use as a single URL query value, so a multi-word or
+// special-character search term doesn't break the request.
+pub(crate) fn url_encode(input: &str) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+// Extracts `Quote`s out of a JSON response body, given the field names that hold the author and
+// the quote text. Handles a bare array as well as an array nested under a "results" or "quotes"
+// key, which covers most quote APIs.
+pub(crate) fn quotes_from_json(body: &str, author_field: &str, quote_field: &str) -> Result<Vec<Quote>, String> {
+    let value: serde_json::Value = serde_json::from_str(body).map_err(|e| e.to_string())?;
+    let items: Vec<serde_json::Value> = match value {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Object(ref map) => {
+            match map.get("results").or_else(|| map.get("quotes")).and_then(|v| v.as_array()) {
+                Some(items) => items.clone(),
+                None => vec![value.clone()],
+            }
+        }
+        _ => return Err("unexpected response shape".to_string()),
+    };
+    Ok(items.iter()
+        .filter_map(|item| {
+            let author = item.get(author_field)?.as_str()?.to_string();
+            let quote = item.get(quote_field)?.as_str()?.to_string();
+            Some(Quote { author, quote, tags: Vec::new() })
+        })
+        .collect())
+}
@@ -0,0 +1,20 @@
+use super::super::util::http_get;
+use super::super::Quote;
+use super::{config_str, quotes_from_json, url_encode, QuoteClient};
+
+/// Fetches quotes from a Quotable-style JSON API (https://api.quotable.io by default).
+pub struct QuotableClient;
+
+impl QuoteClient for QuotableClient {
+    fn fetch(&self, query: Option<&str>) -> Result<Vec<Quote>, String> {
+        let endpoint = config_str("quotable_endpoint", "https://api.quotable.io/quotes");
+        let url = match query {
+            Some(q) => format!("{}?tags={}", endpoint, url_encode(q)),
+            None => endpoint,
+        };
+        let body = http_get(&url)?;
+        let author_field = config_str("quotable_author_field", "author");
+        let quote_field = config_str("quotable_quote_field", "content");
+        quotes_from_json(&body, &author_field, &quote_field)
+    }
+}
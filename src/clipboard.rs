@@ -0,0 +1,51 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[cfg(all(unix, not(target_os = "macos")))]
+use super::util::is_on_path;
+
+//------------------------------------------------------------------------------------------------------
+// Clipboard
+//------------------------------------------------------------------------------------------------------
+
+/// Copies `text` onto the OS clipboard, detecting the platform tool at runtime.
+pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let mut command = detect_command()?;
+    let mut child = command
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    {
+        let stdin = child.stdin.as_mut().ok_or("failed to open clipboard tool stdin".to_string())?;
+        stdin.write_all(text.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err("clipboard tool exited with an error".to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn detect_command() -> Result<Command, String> {
+    Ok(Command::new("pbcopy"))
+}
+
+#[cfg(target_os = "windows")]
+fn detect_command() -> Result<Command, String> {
+    Ok(Command::new("clip"))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn detect_command() -> Result<Command, String> {
+    if is_on_path("wl-copy") {
+        return Ok(Command::new("wl-copy"));
+    }
+    if is_on_path("xclip") {
+        let mut command = Command::new("xclip");
+        command.arg("-selection").arg("clipboard");
+        return Ok(command);
+    }
+    Err("no clipboard tool found (tried wl-copy, xclip)".to_string())
+}
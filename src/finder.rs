@@ -0,0 +1,52 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use super::util::is_on_path;
+
+//------------------------------------------------------------------------------------------------------
+// Finder
+//------------------------------------------------------------------------------------------------------
+
+pub trait Finder {
+    fn find(&self, lines: &[String]) -> Result<Option<String>, String>;
+}
+
+pub struct ExternalFinder {
+    binary: String,
+}
+
+impl ExternalFinder {
+    /// Looks for a supported fuzzy finder binary on `PATH`, trying `fzf` then `sk`.
+    pub fn detect() -> Option<ExternalFinder> {
+        for binary in &["fzf", "sk"] {
+            if is_on_path(binary) {
+                return Some(ExternalFinder { binary: binary.to_string() });
+            }
+        }
+        None
+    }
+}
+
+impl Finder for ExternalFinder {
+    fn find(&self, lines: &[String]) -> Result<Option<String>, String> {
+        let mut child = Command::new(&self.binary)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| e.to_string())?;
+        {
+            let stdin = child.stdin.as_mut().ok_or("failed to open finder stdin".to_string())?;
+            stdin.write_all(lines.join("\n").as_bytes()).map_err(|e| e.to_string())?;
+        }
+        let output = child.wait_with_output().map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        let selected = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if selected.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(selected))
+        }
+    }
+}
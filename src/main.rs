@@ -5,16 +5,28 @@ extern crate jfs;
 extern crate log;
 #[macro_use]
 extern crate prettytable;
+extern crate rand;
 #[macro_use]
 extern crate serde_derive;
+extern crate serde_json;
 extern crate shellexpand;
 extern crate simplelog;
 extern crate textwrap;
+extern crate toml;
 extern crate unicode_width;
 
+mod clients;
+mod clipboard;
+mod finder;
+mod repo;
+mod util;
+
 use clap::{App, Arg, ArgMatches, SubCommand};
+use clients::QuoteClient;
+use finder::{ExternalFinder, Finder};
 use jfs::Store as Store;
 use prettytable::{format, Row, Table};
+use rand::Rng;
 use simplelog::*;
 use std::collections::BTreeMap;
 use std::fs::OpenOptions;
@@ -42,6 +54,24 @@ fn main() {
             .about("Add a quote"))
         .subcommand(SubCommand::with_name("list")
             .about("Prints all quotes")
+            .arg(Arg::with_name("long-format")
+                .help("Display all information such as IDs")
+                .short("l")
+                .long("long-format"))
+            .arg(Arg::with_name("copy")
+                .help("Pick a quote (fuzzy finder if installed, else ID prompt) and copy it to the clipboard instead of listing")
+                .short("c")
+                .long("copy"))
+            .arg(Arg::with_name("tag")
+                .help("Only list quotes carrying this tag")
+                .long("tag")
+                .takes_value(true)))
+        .subcommand(SubCommand::with_name("search")
+            .about("Search quotes by author, text, or tag")
+            .arg(Arg::with_name("QUERY")
+                .help("Text to search for")
+                .required(true)
+                .takes_value(true))
             .arg(Arg::with_name("long-format")
                 .help("Display all information such as IDs")
                 .short("l")
@@ -49,10 +79,71 @@ fn main() {
         .subcommand(SubCommand::with_name("delete")
             .about("Delete a quote by ID")
             .arg(Arg::with_name("QUOTE_ID")
-                .help("ID of the quote you want to delete")
-                .required(true)
+                .help("ID of the quote you want to delete. If omitted, pick it from a fuzzy finder, or type it at a prompt if none is installed")
+                .required(false)
                 .takes_value(true)
                 .multiple(false)))
+        .subcommand(SubCommand::with_name("show")
+            .about("Show a single quote")
+            .arg(Arg::with_name("QUOTE_ID")
+                .help("ID of the quote you want to show. If omitted, pick it from a fuzzy finder, or type it at a prompt if none is installed")
+                .required(false)
+                .takes_value(true)
+                .multiple(false))
+            .arg(Arg::with_name("long-format")
+                .help("Display all information such as IDs")
+                .short("l")
+                .long("long-format")))
+        .subcommand(SubCommand::with_name("copy")
+            .about("Copy a quote to the clipboard")
+            .arg(Arg::with_name("QUOTE_ID")
+                .help("ID of the quote you want to copy. If omitted, pick it from a fuzzy finder, or type it at a prompt if none is installed")
+                .required(false)
+                .takes_value(true)
+                .multiple(false))
+            .arg(Arg::with_name("with-author")
+                .help("Include the author in the copied text, as \"quote\" — author")
+                .long("with-author")))
+        .subcommand(SubCommand::with_name("repo")
+            .about("Manage remote quote repositories")
+            .subcommand(SubCommand::with_name("add")
+                .about("Import a remote quote repository and remember it for future syncs")
+                .arg(Arg::with_name("URL")
+                    .help("Git URL or HTTP URL of a remote source of quotes")
+                    .required(true)
+                    .takes_value(true)))
+            .subcommand(SubCommand::with_name("sync")
+                .about("Re-pull every configured remote repository"))
+            .subcommand(SubCommand::with_name("browse")
+                .about("List configured remote repositories")))
+        .subcommand(SubCommand::with_name("fetch")
+            .about("Fetch quotes from an online quote source and save them")
+            .arg(Arg::with_name("QUERY")
+                .help("Optional search term to narrow the fetched quotes")
+                .required(false)
+                .takes_value(true))
+            .arg(Arg::with_name("source")
+                .help("Which configured quote source to fetch from")
+                .long("source")
+                .takes_value(true)
+                .possible_values(&["quotable", "dummyjson"])
+                .default_value("quotable")))
+        .subcommand(SubCommand::with_name("random")
+            .about("Prints one randomly picked quote, handy for a MOTD or login banner")
+            .arg(Arg::with_name("format")
+                .help("Template to render the quote with, using {author}, {quote} and {id} placeholders")
+                .long("format")
+                .takes_value(true))
+            .arg(Arg::with_name("plain")
+                .help("Print the quote as plain text instead of a table")
+                .long("plain")))
+        .subcommand(SubCommand::with_name("widget")
+            .about("Print a shell snippet that wires `random` into your prompt or login banner")
+            .arg(Arg::with_name("SHELL")
+                .help("Shell to generate the snippet for")
+                .required(true)
+                .takes_value(true)
+                .possible_values(&["bash", "zsh", "fish"])))
         .get_matches();
     if let Err(e) = run(matches) {
         error!("{}", e);
@@ -68,7 +159,7 @@ fn main() {
 fn run(matches: ArgMatches) -> Result<(), String> {
     // App setup
     // config file
-    let (app_config, config_file_found) = get_config_parameter();
+    let (mut app_config, config_file_found) = get_config_parameter();
     // Init logger
     let term_min_log_level = match matches.occurrences_of("verbose") {
         0 => LevelFilter::Error,
@@ -113,7 +204,14 @@ fn run(matches: ArgMatches) -> Result<(), String> {
     match matches.subcommand() {
         ("add", Some(_)) => cmd_quote_add(r),
         ("list", Some(m)) => cmd_quote_list(r, m),
+        ("search", Some(m)) => cmd_quote_search(r, m),
         ("delete", Some(m)) => cmd_quote_delete(r, m),
+        ("show", Some(m)) => cmd_quote_show(r, m),
+        ("copy", Some(m)) => cmd_quote_copy(r, m),
+        ("repo", Some(m)) => cmd_repo(r, m, &mut app_config),
+        ("fetch", Some(m)) => cmd_quote_fetch(r, m),
+        ("random", Some(m)) => cmd_quote_random(r, m),
+        ("widget", Some(m)) => cmd_widget(m),
         _ => {
             println!("No default action. Please see qquotes --help for more information");
             Ok(())
@@ -129,12 +227,14 @@ fn run(matches: ArgMatches) -> Result<(), String> {
 struct AppConfig<> {
     log_path: String,
     data_path: String,
+    remotes: Vec<String>,
 }
 
 fn get_config_parameter() -> (AppConfig, bool) {
     let default = AppConfig {
         log_path: DEFAULT_PATH_LOG_FILE.to_string(),
         data_path: DEFAULT_PATH_DATA_FILE.to_string(),
+        remotes: Vec::new(),
     };
     match Path::new(&shellexpand::tilde(PATH_CONFIG_FILE).into_owned()).exists() {
         true => {
@@ -149,6 +249,10 @@ fn get_config_parameter() -> (AppConfig, bool) {
                 Ok(v) => app_config.data_path = v,
                 Err(_) => (),
             }
+            match settings.get::<Vec<String>>("remotes") {
+                Ok(v) => app_config.remotes = v,
+                Err(_) => (),
+            }
             return (app_config, true);
         }
         false => (),
@@ -156,6 +260,33 @@ fn get_config_parameter() -> (AppConfig, bool) {
     (default, false)
 }
 
+// Rewrites config.toml with the current in-memory AppConfig, used after `repo add` records a
+// new remote. Loads the file as a generic TOML table first and only overwrites the keys
+// AppConfig knows about, so unrelated keys (e.g. the `fetch` clients' endpoint/field settings)
+// round-trip untouched.
+fn save_config_parameter(app_config: &AppConfig) -> Result<(), String> {
+    let path = shellexpand::tilde(PATH_CONFIG_FILE).into_owned();
+    if let Some(parent) = Path::new(&path).parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut doc: toml::value::Table = if Path::new(&path).exists() {
+        let existing = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        match toml::from_str::<toml::Value>(&existing) {
+            Ok(toml::Value::Table(table)) => table,
+            _ => toml::value::Table::new(),
+        }
+    } else {
+        toml::value::Table::new()
+    };
+    doc.insert("path_log_file".to_string(), toml::Value::String(app_config.log_path.clone()));
+    doc.insert("path_data_file".to_string(), toml::Value::String(app_config.data_path.clone()));
+    doc.insert("remotes".to_string(), toml::Value::Array(
+        app_config.remotes.iter().map(|remote| toml::Value::String(remote.clone())).collect()
+    ));
+    let contents = toml::to_string(&toml::Value::Table(doc)).map_err(|e| e.to_string())?;
+    std::fs::write(&path, contents).map_err(|e| e.to_string())
+}
+
 //------------------------------------------------------------------------------------------------------
 // Commands
 //------------------------------------------------------------------------------------------------------
@@ -171,9 +302,16 @@ fn cmd_quote_add(r: Repository) -> Result<(), String> {
         Ok(v) => quote = v,
         Err(e) => return Err(e.to_string()),
     };
+    let tags_input: String;
+    match ask("tags (comma-separated)") {
+        Ok(v) => tags_input = v,
+        Err(e) => return Err(e.to_string()),
+    };
+    let tags = parse_tags(&tags_input);
     match r.save_quote(Quote {
         author,
         quote,
+        tags,
     }) {
         Ok(_) => {
             Ok(())
@@ -183,8 +321,15 @@ fn cmd_quote_add(r: Repository) -> Result<(), String> {
 }
 
 fn cmd_quote_list(r: Repository, args: &ArgMatches) -> Result<(), String> {
+    if args.is_present("copy") {
+        return copy_quote(&r, None, false);
+    }
     match r.get_quotes() {
         Ok(quotes) => {
+            let quotes = match args.value_of("tag") {
+                Some(tag) => filter_quotes_by_tag(quotes, tag),
+                None => quotes,
+            };
             if quotes.len() > 0 {
                 format_and_display_quotes_list(quotes, args.is_present("long-format"));
             } else {
@@ -196,13 +341,135 @@ fn cmd_quote_list(r: Repository, args: &ArgMatches) -> Result<(), String> {
     }
 }
 
-fn cmd_quote_delete(r: Repository, args: &ArgMatches) -> Result<(), String> {
-    let id: String;
-    match args.value_of("QUOTE_ID") {
-        Some(v) => id = v.to_string(),
-        None => return Err("Missing QUOTE_ID".to_string()),
+fn cmd_quote_search(r: Repository, args: &ArgMatches) -> Result<(), String> {
+    let query = match args.value_of("QUERY") {
+        Some(v) => v,
+        None => return Err("Missing QUERY".to_string()),
+    };
+    let quotes = r.get_quotes()?;
+    let matches = search_quotes(quotes, query);
+    if matches.len() > 0 {
+        format_and_display_quotes_list(matches, args.is_present("long-format"));
+    } else {
+        println!("No quote matches \"{}\".", query);
+    }
+    Ok(())
+}
+
+fn cmd_quote_copy(r: Repository, args: &ArgMatches) -> Result<(), String> {
+    copy_quote(&r, args.value_of("QUOTE_ID"), args.is_present("with-author"))
+}
+
+fn copy_quote(r: &Repository, provided_id: Option<&str>, with_author: bool) -> Result<(), String> {
+    let id = resolve_quote_id(r, provided_id)?;
+    let quote = r.get_quote(&id)?;
+    let text = if with_author {
+        format!("\"{}\" — {}", quote.quote, quote.author)
+    } else {
+        quote.quote
+    };
+    clipboard::copy_to_clipboard(&text)
+}
+
+fn cmd_quote_fetch(r: Repository, args: &ArgMatches) -> Result<(), String> {
+    let client: Box<dyn QuoteClient> = match args.value_of("source").unwrap_or("quotable") {
+        "dummyjson" => Box::new(clients::dummyjson::DummyJsonClient),
+        _ => Box::new(clients::quotable::QuotableClient),
+    };
+    let quotes = client.fetch(args.value_of("QUERY"))?;
+    let mut saved = 0;
+    for quote in quotes {
+        r.save_quote(quote)?;
+        saved += 1;
+    }
+    info!("fetch_saved_quotes {}", saved);
+    println!("Fetched and saved {} quote(s).", saved);
+    Ok(())
+}
+
+fn cmd_quote_random(r: Repository, args: &ArgMatches) -> Result<(), String> {
+    let quotes = r.get_quotes()?;
+    if quotes.is_empty() {
+        println!("There is no quote saved.");
+        return Ok(());
+    }
+    let ids: Vec<&String> = quotes.keys().collect();
+    let id = ids[rand::thread_rng().gen_range(0, ids.len())].clone();
+    let quote = &quotes[&id];
+    match args.value_of("format") {
+        Some(template) => println!("{}", render_quote_template(template, &id, quote)),
+        None if args.is_present("plain") => println!("{}", render_quote_template("{quote} — {author}", &id, quote)),
+        None => {
+            let mut single: BTreeMap<String, Quote> = BTreeMap::new();
+            single.insert(id.clone(), quote.clone());
+            format_and_display_quotes_list(single, false);
+        }
+    }
+    Ok(())
+}
+
+// Substitutes placeholders in a single pass so that a quote's text or author containing a
+// literal "{quote}"/"{id}" is never re-matched by a later substitution.
+fn render_quote_template(template: &str, id: &str, quote: &Quote) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while !rest.is_empty() {
+        if rest.starts_with("{author}") {
+            result.push_str(&quote.author);
+            rest = &rest["{author}".len()..];
+        } else if rest.starts_with("{quote}") {
+            result.push_str(&quote.quote);
+            rest = &rest["{quote}".len()..];
+        } else if rest.starts_with("{id}") {
+            result.push_str(id);
+            rest = &rest["{id}".len()..];
+        } else {
+            let ch = rest.chars().next().unwrap();
+            result.push(ch);
+            rest = &rest[ch.len_utf8()..];
+        }
+    }
+    result
+}
+
+fn cmd_widget(args: &ArgMatches) -> Result<(), String> {
+    let shell = match args.value_of("SHELL") {
+        Some(v) => v,
+        None => return Err("Missing SHELL".to_string()),
+    };
+    // Hooks into each shell's own "about to draw a prompt" mechanism, rather than just printing
+    // a quote once when the snippet is sourced.
+    let snippet = match shell {
+        "bash" => "qquotes_motd() {\n    qquotes random --plain\n}\nPROMPT_COMMAND=\"qquotes_motd${PROMPT_COMMAND:+; $PROMPT_COMMAND}\"".to_string(),
+        "zsh" => "qquotes_motd() {\n    qquotes random --plain\n}\nautoload -Uz add-zsh-hook\nadd-zsh-hook precmd qquotes_motd".to_string(),
+        "fish" => "function qquotes_motd --on-event fish_prompt\n    qquotes random --plain\nend".to_string(),
+        _ => return Err(format!("unsupported shell: {}", shell)),
     };
-    match r.delete_quote(&id.to_string()) {
+    println!("{}", snippet);
+    Ok(())
+}
+
+fn cmd_repo(r: Repository, args: &ArgMatches, app_config: &mut AppConfig) -> Result<(), String> {
+    match args.subcommand() {
+        ("add", Some(m)) => {
+            let url = match m.value_of("URL") {
+                Some(v) => v,
+                None => return Err("Missing URL".to_string()),
+            };
+            repo::add_remote(&r, app_config, url)
+        }
+        ("sync", Some(_)) => repo::sync_remotes(&r, app_config),
+        ("browse", Some(_)) => repo::browse_remotes(app_config),
+        _ => {
+            println!("No default action. Please see qquotes repo --help for more information");
+            Ok(())
+        }
+    }
+}
+
+fn cmd_quote_delete(r: Repository, args: &ArgMatches) -> Result<(), String> {
+    let id = resolve_quote_id(&r, args.value_of("QUOTE_ID"))?;
+    match r.delete_quote(&id) {
         Ok(_) => {
             Ok(())
         }
@@ -210,6 +477,44 @@ fn cmd_quote_delete(r: Repository, args: &ArgMatches) -> Result<(), String> {
     }
 }
 
+fn cmd_quote_show(r: Repository, args: &ArgMatches) -> Result<(), String> {
+    let id = resolve_quote_id(&r, args.value_of("QUOTE_ID"))?;
+    let quote = r.get_quote(&id)?;
+    let mut quotes: BTreeMap<String, Quote> = BTreeMap::new();
+    quotes.insert(id, quote);
+    format_and_display_quotes_list(quotes, args.is_present("long-format"));
+    Ok(())
+}
+
+//------------------------------------------------------------------------------------------------------
+// Quote picking
+//------------------------------------------------------------------------------------------------------
+
+// Resolves a QUOTE_ID either from the CLI argument or, when it is missing, by letting the
+// user pick interactively: through a fuzzy finder if one is installed, otherwise through the
+// same `ask()` prompt used by `add`.
+fn resolve_quote_id(r: &Repository, provided: Option<&str>) -> Result<String, String> {
+    if let Some(v) = provided {
+        return Ok(v.to_string());
+    }
+    let quotes = r.get_quotes()?;
+    if quotes.is_empty() {
+        return Err("There is no quote saved.".to_string());
+    }
+    match ExternalFinder::detect() {
+        Some(finder) => {
+            let lines: Vec<String> = quotes.iter()
+                .map(|(id, quote)| format!("{}\t{}\t{}", id, quote.author, quote.quote))
+                .collect();
+            match finder.find(&lines)? {
+                Some(selected) => Ok(selected.splitn(2, '\t').next().unwrap_or("").to_string()),
+                None => Err("No quote selected".to_string()),
+            }
+        }
+        None => ask("QUOTE_ID").map_err(|e| e.to_string()),
+    }
+}
+
 //------------------------------------------------------------------------------------------------------
 // Format ask
 //------------------------------------------------------------------------------------------------------
@@ -251,14 +556,46 @@ fn format_and_display_quotes_list(quotes: BTreeMap<String, Quote>, long_format:
     display_quotes_table(titles, rows);
 }
 
+//------------------------------------------------------------------------------------------------------
+// Tags and search
+//------------------------------------------------------------------------------------------------------
+
+fn parse_tags(input: &str) -> Vec<String> {
+    input.split(',')
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+fn filter_quotes_by_tag(quotes: BTreeMap<String, Quote>, tag: &str) -> BTreeMap<String, Quote> {
+    let tag = tag.to_lowercase();
+    quotes.into_iter()
+        .filter(|(_, quote)| quote.tags.iter().any(|t| t.to_lowercase() == tag))
+        .collect()
+}
+
+// Case-insensitive substring match across author, quote text, and tags.
+fn search_quotes(quotes: BTreeMap<String, Quote>, query: &str) -> BTreeMap<String, Quote> {
+    let query = query.to_lowercase();
+    quotes.into_iter()
+        .filter(|(_, quote)| {
+            quote.author.to_lowercase().contains(&query)
+                || quote.quote.to_lowercase().contains(&query)
+                || quote.tags.iter().any(|tag| tag.to_lowercase().contains(&query))
+        })
+        .collect()
+}
+
 //------------------------------------------------------------------------------------------------------
 // Repository
 //------------------------------------------------------------------------------------------------------
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Quote {
     author: String,
     quote: String,
+    #[serde(default)]
+    tags: Vec<String>,
 }
 
 struct Repository {
@@ -294,6 +631,11 @@ impl Repository {
         }
     }
 
+    fn get_quote(&self, id: &str) -> Result<Quote, String> {
+        trace!("repository_get_quote id: {}", id);
+        self.store.get::<Quote>(id).map_err(|e| e.to_string())
+    }
+
     fn delete_quote(&self, id: &String) -> Result<(), String> {
         trace!("repository_delete_quote id: {}", id);
         match self.store.delete(id) {
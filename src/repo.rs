@@ -0,0 +1,173 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::util::http_get;
+use super::{save_config_parameter, AppConfig, Quote, Repository};
+
+static REPOS_DIR: &'static str = "~/.config/qquotes/repos";
+
+//------------------------------------------------------------------------------------------------------
+// Repo subcommands
+//------------------------------------------------------------------------------------------------------
+
+pub fn add_remote(r: &Repository, app_config: &mut AppConfig, url: &str) -> Result<(), String> {
+    merge_quotes(r, fetch_remote_quotes(url)?)?;
+    if !app_config.remotes.iter().any(|remote| remote == url) {
+        app_config.remotes.push(url.to_string());
+        save_config_parameter(app_config)?;
+    }
+    Ok(())
+}
+
+pub fn sync_remotes(r: &Repository, app_config: &AppConfig) -> Result<(), String> {
+    for url in &app_config.remotes {
+        merge_quotes(r, fetch_remote_quotes(url)?)?;
+    }
+    Ok(())
+}
+
+pub fn browse_remotes(app_config: &AppConfig) -> Result<(), String> {
+    if app_config.remotes.is_empty() {
+        println!("No remote repository configured. Add one with `qquotes repo add <url>`.");
+    }
+    for url in &app_config.remotes {
+        println!("{}", url);
+    }
+    Ok(())
+}
+
+//------------------------------------------------------------------------------------------------------
+// Merging
+//------------------------------------------------------------------------------------------------------
+
+// Saves every quote not already present, deduplicating by a stable hash of author+quote so
+// re-syncing the same remote is idempotent.
+fn merge_quotes(r: &Repository, quotes: Vec<Quote>) -> Result<(), String> {
+    let existing = r.get_quotes()?;
+    let mut seen: HashSet<u64> = existing.values().map(quote_hash).collect();
+    let mut added = 0;
+    for quote in quotes {
+        if seen.insert(quote_hash(&quote)) {
+            r.save_quote(quote)?;
+            added += 1;
+        }
+    }
+    info!("repo_merge_quotes added: {}", added);
+    Ok(())
+}
+
+fn quote_hash(quote: &Quote) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    quote.author.hash(&mut hasher);
+    quote.quote.hash(&mut hasher);
+    hasher.finish()
+}
+
+//------------------------------------------------------------------------------------------------------
+// Fetching
+//------------------------------------------------------------------------------------------------------
+
+fn fetch_remote_quotes(url: &str) -> Result<Vec<Quote>, String> {
+    if is_git_url(url) {
+        fetch_from_git(url)
+    } else {
+        parse_quotes(&http_get(url)?)
+    }
+}
+
+// A remote is treated as a git repository to clone when it uses the `git@…` form, ends in
+// `.git`, or is an http(s) URL on github.com, gitlab.com or bitbucket.org that does not end in
+// `.json`/`.toml`. Every other URL is downloaded directly and parsed as a quote file.
+fn is_git_url(url: &str) -> bool {
+    if url.starts_with("git@") || url.ends_with(".git") {
+        return true;
+    }
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return false;
+    }
+    if url.ends_with(".json") || url.ends_with(".toml") {
+        return false;
+    }
+    static FORGE_HOSTS: &'static [&'static str] = &["github.com/", "gitlab.com/", "bitbucket.org/"];
+    FORGE_HOSTS.iter().any(|host| url.contains(host))
+}
+
+fn fetch_from_git(url: &str) -> Result<Vec<Quote>, String> {
+    let dir = clone_target_dir(url);
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    }
+    if let Some(parent) = dir.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let status = Command::new("git")
+        .arg("clone")
+        .arg("--depth").arg("1")
+        .arg(url)
+        .arg(&dir)
+        .status()
+        .map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(format!("failed to clone {}", url));
+    }
+    let mut quotes = Vec::new();
+    collect_quote_files(&dir, &mut quotes)?;
+    Ok(quotes)
+}
+
+fn clone_target_dir(url: &str) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    Path::new(&shellexpand::tilde(REPOS_DIR).into_owned()).join(format!("{:x}", hasher.finish()))
+}
+
+// Walks the cloned tree for JSON/TOML files and parses each as quotes, skipping the `.git`
+// directory and any file that doesn't parse as one of ours (a cloned repo routinely carries
+// unrelated JSON/TOML such as `package.json` or its own `Cargo.toml`).
+fn collect_quote_files(dir: &Path, quotes: &mut Vec<Quote>) -> Result<(), String> {
+    for entry in fs::read_dir(dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.is_dir() {
+            if path.file_name().and_then(|n| n.to_str()) == Some(".git") {
+                continue;
+            }
+            collect_quote_files(&path, quotes)?;
+        } else if is_quote_file(&path) {
+            let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+            match parse_quotes(&content) {
+                Ok(qs) => quotes.extend(qs),
+                Err(e) => warn!("repo_skip_unparseable_file path: {:?} error: {}", path, e),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_quote_file(path: &Path) -> bool {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("json") | Some("toml") => true,
+        _ => false,
+    }
+}
+
+// A remote source is either a bare JSON array of quotes or a TOML document with a top-level
+// `[[quotes]]` array of tables.
+fn parse_quotes(content: &str) -> Result<Vec<Quote>, String> {
+    if let Ok(quotes) = serde_json::from_str::<Vec<Quote>>(content) {
+        return Ok(quotes);
+    }
+
+    #[derive(Deserialize)]
+    struct QuotesFile {
+        quotes: Vec<Quote>,
+    }
+    if let Ok(file) = toml::from_str::<QuotesFile>(content) {
+        return Ok(file.quotes);
+    }
+
+    Err("could not parse remote content as a JSON array of quotes or a TOML [[quotes]] table".to_string())
+}
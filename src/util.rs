@@ -0,0 +1,25 @@
+use std::path::Path;
+use std::process::Command;
+
+//------------------------------------------------------------------------------------------------------
+// Shared helpers
+//------------------------------------------------------------------------------------------------------
+
+/// Checks whether a file named `binary` exists in some directory on `PATH`; its executable
+/// permission is not checked.
+pub fn is_on_path(binary: &str) -> bool {
+    match std::env::var_os("PATH") {
+        Some(path) => std::env::split_paths(&path).any(|dir| Path::new(&dir).join(binary).is_file()),
+        None => false,
+    }
+}
+
+/// Downloads `url` with curl, treating an HTTP error status as a failure so callers never get
+/// handed an error page to parse.
+pub fn http_get(url: &str) -> Result<String, String> {
+    let output = Command::new("curl").arg("-sL").arg("-f").arg(url).output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!("failed to fetch {}", url));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}